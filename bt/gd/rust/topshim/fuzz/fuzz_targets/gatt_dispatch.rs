@@ -0,0 +1,15 @@
+//! Coverage-guided fuzz target for the GATT server event dispatch path.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use bt_topshim::profiles::gatt::GattServerDispatch;
+use bt_topshim_fuzz::FuzzGattServerEvent;
+
+fuzz_target!(|events: Vec<FuzzGattServerEvent>| {
+    let dispatch = GattServerDispatch::new_for_fuzz();
+    for event in events {
+        event.drive(&dispatch);
+    }
+});
@@ -0,0 +1,12 @@
+//! Coverage-guided fuzz target for the SDP record parser.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use bt_topshim::profiles::sdp::SdpDispatch;
+use bt_topshim_fuzz::FuzzSdpRecord;
+
+fuzz_target!(|record: FuzzSdpRecord| {
+    let dispatch = SdpDispatch::new_for_fuzz();
+    record.drive(&dispatch);
+});
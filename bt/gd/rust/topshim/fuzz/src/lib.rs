@@ -0,0 +1,157 @@
+//! Shared fuzzing helpers for the topshim profile parsers.
+//!
+//! The C++ stack delivers profile events to Rust as a set of scalar FFI
+//! callback arguments (handles, status codes, length-prefixed buffers). The
+//! fuzz targets in `fuzz_targets/` never see those arguments directly; instead
+//! they let the fuzzer synthesize them through [`arbitrary`] so that every
+//! reachable field is driven by coverage feedback rather than by a hand-rolled
+//! byte layout. The structs here mirror the shape of those callback arguments
+//! and know how to replay themselves through the real profile dispatch. The
+//! `new_for_fuzz()` constructors and `SdpRecord::from_bytes` entry points they
+//! drive live in the `profiles` module alongside the code under test.
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use bt_topshim::btif::{BtStatus, RawAddress, Uuid};
+use bt_topshim::profiles::gatt::{GattServerCallbacks, GattServerDispatch};
+use bt_topshim::profiles::sdp::{SdpCallbacks, SdpDispatch, SdpRecord};
+
+/// Number of bytes we are willing to let the fuzzer pour into a single
+/// length-prefixed attribute blob. Real GATT attributes are bounded by the ATT
+/// MTU, so an unbounded `Vec<u8>` would only waste the fuzzer's budget.
+const MAX_ATTR_LEN: usize = 512;
+
+/// A length-bounded attribute payload. The `arbitrary` derive has no field
+/// attribute for capping a `Vec`, so the bound lives in a hand-written impl.
+#[derive(Debug)]
+pub struct AttrBlob(pub Vec<u8>);
+
+impl<'a> Arbitrary<'a> for AttrBlob {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=MAX_ATTR_LEN)?;
+        let mut out = vec![0u8; len];
+        u.fill_buffer(&mut out)?;
+        Ok(AttrBlob(out))
+    }
+}
+
+/// A fuzzer-built BD_ADDR. `RawAddress` is `#[repr(C)]` on the FFI boundary, so
+/// we reconstruct it from six arbitrary octets exactly like the C++ side does.
+#[derive(Debug, Arbitrary)]
+pub struct FuzzAddress {
+    pub octets: [u8; 6],
+}
+
+impl From<FuzzAddress> for RawAddress {
+    fn from(addr: FuzzAddress) -> Self {
+        RawAddress { val: addr.octets }
+    }
+}
+
+/// A fuzzer-built 128-bit service/characteristic UUID.
+#[derive(Debug, Arbitrary)]
+pub struct FuzzUuid {
+    pub bytes: [u8; 16],
+}
+
+impl From<FuzzUuid> for Uuid {
+    fn from(uuid: FuzzUuid) -> Self {
+        Uuid::from(uuid.bytes)
+    }
+}
+
+/// One structurally valid GATT server event, reconstructed from the arguments
+/// the `bindings`/`btif` layer would have received off the C++ stack.
+#[derive(Debug, Arbitrary)]
+pub enum FuzzGattServerEvent {
+    Registered {
+        status: u8,
+        server_if: i32,
+        app_uuid: FuzzUuid,
+    },
+    Connection {
+        conn_id: i32,
+        server_if: i32,
+        connected: bool,
+        addr: FuzzAddress,
+    },
+    AttributeWrite {
+        conn_id: i32,
+        trans_id: i32,
+        addr: FuzzAddress,
+        attr_handle: i32,
+        offset: i32,
+        need_rsp: bool,
+        is_prep: bool,
+        value: AttrBlob,
+    },
+}
+
+impl FuzzGattServerEvent {
+    /// Replay the event through the real GATT server dispatch. The dispatch is
+    /// the same one `btif` installs at startup; a panic or UB here is a bug in
+    /// the parser, which is exactly what the fuzzer is hunting for.
+    pub fn drive(self, dispatch: &GattServerDispatch) {
+        match self {
+            FuzzGattServerEvent::Registered { status, server_if, app_uuid } => {
+                dispatch.dispatch(GattServerCallbacks::Register(
+                    BtStatus::from(status as u32),
+                    server_if,
+                    app_uuid.into(),
+                ));
+            }
+            FuzzGattServerEvent::Connection { conn_id, server_if, connected, addr } => {
+                dispatch.dispatch(GattServerCallbacks::Connection(
+                    conn_id,
+                    server_if,
+                    connected,
+                    addr.into(),
+                ));
+            }
+            FuzzGattServerEvent::AttributeWrite {
+                conn_id,
+                trans_id,
+                addr,
+                attr_handle,
+                offset,
+                need_rsp,
+                is_prep,
+                value,
+            } => {
+                dispatch.dispatch(GattServerCallbacks::RequestWrite(
+                    conn_id,
+                    trans_id,
+                    addr.into(),
+                    attr_handle,
+                    offset,
+                    need_rsp,
+                    is_prep,
+                    value.0,
+                ));
+            }
+        }
+    }
+}
+
+/// One SDP search result, reconstructed from the record array the C++ SDP
+/// client hands back. The record blob is the classic malformed-input surface:
+/// length-prefixed attribute lists parsed straight out of a remote response.
+#[derive(Debug, Arbitrary)]
+pub struct FuzzSdpRecord {
+    pub status: u8,
+    pub addr: FuzzAddress,
+    pub uuid: FuzzUuid,
+    pub record: AttrBlob,
+}
+
+impl FuzzSdpRecord {
+    /// Replay the SDP search completion through the real dispatch.
+    pub fn drive(self, dispatch: &SdpDispatch) {
+        dispatch.dispatch(SdpCallbacks::SearchComplete(
+            BtStatus::from(self.status as u32),
+            self.addr.into(),
+            self.uuid.into(),
+            SdpRecord::from_bytes(&self.record.0),
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Common types shared across the Bluetooth interface shim.
+//!
+//! These mirror the `#[repr(C)]` primitives the C++ stack hands across the FFI
+//! boundary: the status code returned by every interface call, a BD_ADDR, and a
+//! 128-bit UUID.
+
+/// Status returned by `btif`/profile interface calls. Values match the C++
+/// `bt_status_t` enum so they can be reconstructed from the raw FFI integer.
+#[derive(Clone, Copy, Debug, FromPrimitive, ToPrimitive, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BtStatus {
+    Success = 0,
+    Fail,
+    NotReady,
+    NoMemory,
+    Busy,
+    Done,
+    Unsupported,
+    ParamInvalid,
+    Unhandled,
+    AuthFailure,
+    RemoteDeviceDown,
+    AuthRejected,
+    JniEnvironmentError,
+    JniThreadAttachError,
+    WakeLockError,
+    Unknown = 0xff,
+}
+
+impl From<u32> for BtStatus {
+    fn from(item: u32) -> Self {
+        num::FromPrimitive::from_u32(item).unwrap_or(BtStatus::Unknown)
+    }
+}
+
+/// A Bluetooth device address (BD_ADDR).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct RawAddress {
+    pub val: [u8; 6],
+}
+
+/// A 128-bit service or characteristic UUID.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct Uuid {
+    pub uu: [u8; 16],
+}
+
+impl From<[u8; 16]> for Uuid {
+    fn from(uu: [u8; 16]) -> Self {
+        Uuid { uu }
+    }
+}
@@ -6,5 +6,7 @@ extern crate num_derive;
 
 pub mod bindings;
 pub mod btif;
+pub mod metrics;
 pub mod profiles;
+pub mod sandbox;
 pub mod topstack;
@@ -0,0 +1,199 @@
+//! Structured metrics/telemetry pipeline.
+//!
+//! `btif` and `profiles` record Bluetooth events — adapter state transitions,
+//! per-profile connect/disconnect, ACL link quality, pairing outcomes — by
+//! handing them to a [`MetricsDispatcher`]. The dispatcher owns a task on the
+//! shared runtime that batches events, encodes each batch with the generated
+//! protobuf schema (`bluetooth_metrics.proto`) and flushes the frame to a
+//! pluggable [`MetricsSink`]. This gives a stable, versioned wire format for
+//! diagnostics instead of ad-hoc logging.
+
+use std::sync::Arc;
+
+use protobuf::{Message, ProtobufEnum};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::time::{self, Duration};
+
+use crate::topstack::spawn;
+
+// Generated by the Soong `rust_protobuf` module from `bluetooth_metrics.proto`.
+use bt_metrics_proto::bluetooth_metrics::{
+    AclLinkQuality, AdapterState, AdapterStateChanged, BluetoothMetricsBatch,
+    BluetoothMetricsEvent, ConnectionState, PairingOutcome, PairingResult,
+    ProfileConnectionStateChanged,
+};
+
+/// Flush a batch of at most this many events, even if the flush interval has
+/// not elapsed. Keeps a chatty profile from growing the batch without bound.
+const MAX_BATCH: usize = 64;
+
+/// Flush whatever has accumulated at least this often, so low-traffic events
+/// still reach the sink promptly.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A destination for encoded protobuf frames. Implementations are cheap to
+/// call and must not block the runtime for long.
+pub trait MetricsSink: Send + Sync {
+    /// Consume one encoded [`BluetoothMetricsBatch`] frame.
+    fn flush(&self, frame: &[u8]);
+}
+
+/// A single diagnostic event recorded by the stack. Mirrors the `oneof` in
+/// `bluetooth_metrics.proto`; conversion to the wire type happens at flush time.
+#[derive(Clone, Debug)]
+pub enum MetricsEvent {
+    AdapterStateChanged { previous: i32, current: i32 },
+    ProfileConnectionStateChanged { profile: u32, state: i32 },
+    AclLinkQuality { rssi: i32, lsto: u32, retransmit_count: u32 },
+    PairingOutcome { result: i32, hci_status: u32 },
+}
+
+/// Cloneable handle the stack feeds events into. Dropping the last handle lets
+/// the dispatcher task drain its final batch and exit.
+#[derive(Clone)]
+pub struct MetricsDispatcher {
+    tx: UnboundedSender<MetricsEvent>,
+}
+
+impl MetricsDispatcher {
+    /// Start the dispatcher on the shared runtime, flushing to `sink`.
+    pub fn new(sink: Arc<dyn MetricsSink>) -> Self {
+        let (tx, mut rx) = unbounded_channel::<MetricsEvent>();
+
+        spawn(async move {
+            let mut batch: Vec<MetricsEvent> = Vec::with_capacity(MAX_BATCH);
+            let mut ticker = time::interval(FLUSH_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    event = rx.recv() => match event {
+                        Some(event) => {
+                            batch.push(event);
+                            if batch.len() >= MAX_BATCH {
+                                flush_batch(&*sink, &mut batch);
+                            }
+                        }
+                        // All senders dropped: flush the tail and stop.
+                        None => {
+                            flush_batch(&*sink, &mut batch);
+                            break;
+                        }
+                    },
+                    _ = ticker.tick() => flush_batch(&*sink, &mut batch),
+                }
+            }
+        });
+
+        MetricsDispatcher { tx }
+    }
+
+    /// Record one event. Never blocks; if the dispatcher has gone away the
+    /// event is dropped, since telemetry must not stall the caller.
+    pub fn record(&self, event: MetricsEvent) {
+        let _ = self.tx.send(event);
+    }
+}
+
+/// Encode the accumulated events and hand the frame to the sink, then clear the
+/// batch. A no-op when the batch is empty.
+fn flush_batch(sink: &dyn MetricsSink, batch: &mut Vec<MetricsEvent>) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let mut proto_batch = BluetoothMetricsBatch::new();
+    for event in batch.drain(..) {
+        proto_batch.events.push(event.into_proto());
+    }
+
+    match proto_batch.write_to_bytes() {
+        Ok(frame) => sink.flush(&frame),
+        // A serialization failure is a schema bug, not a runtime condition we
+        // can recover from; log and drop rather than retry a poisoned batch.
+        Err(e) => log::warn!("failed to encode metrics batch: {}", e),
+    }
+}
+
+impl MetricsEvent {
+    fn into_proto(self) -> BluetoothMetricsEvent {
+        let mut out = BluetoothMetricsEvent::new();
+        match self {
+            MetricsEvent::AdapterStateChanged { previous, current } => {
+                let mut e = AdapterStateChanged::new();
+                e.set_previous(AdapterState::from_i32(previous).unwrap_or_default());
+                e.set_current(AdapterState::from_i32(current).unwrap_or_default());
+                out.set_adapter_state(e);
+            }
+            MetricsEvent::ProfileConnectionStateChanged { profile, state } => {
+                let mut e = ProfileConnectionStateChanged::new();
+                e.set_profile(profile);
+                e.set_state(ConnectionState::from_i32(state).unwrap_or_default());
+                out.set_profile_connection(e);
+            }
+            MetricsEvent::AclLinkQuality { rssi, lsto, retransmit_count } => {
+                let mut e = AclLinkQuality::new();
+                e.set_rssi(rssi);
+                e.set_lsto(lsto);
+                e.set_retransmit_count(retransmit_count);
+                out.set_acl_link_quality(e);
+            }
+            MetricsEvent::PairingOutcome { result, hci_status } => {
+                let mut e = PairingOutcome::new();
+                e.set_result(PairingResult::from_i32(result).unwrap_or_default());
+                e.set_hci_status(hci_status);
+                out.set_pairing_outcome(e);
+            }
+        }
+        out
+    }
+}
+
+mod sink;
+pub use sink::{FileSink, StatsdSink};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Collects every frame handed to it so a test can inspect what was flushed.
+    #[derive(Default)]
+    struct CapturingSink {
+        frames: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl MetricsSink for CapturingSink {
+        fn flush(&self, frame: &[u8]) {
+            self.frames.lock().unwrap().push(frame.to_vec());
+        }
+    }
+
+    #[test]
+    fn flush_encodes_all_events_and_clears_batch() {
+        let sink = CapturingSink::default();
+        let mut batch = vec![
+            MetricsEvent::AdapterStateChanged { previous: 1, current: 3 },
+            MetricsEvent::ProfileConnectionStateChanged { profile: 7, state: 3 },
+            MetricsEvent::PairingOutcome { result: 1, hci_status: 0 },
+        ];
+
+        flush_batch(&sink, &mut batch);
+
+        assert!(batch.is_empty(), "batch should be drained after a flush");
+        let frames = sink.frames.lock().unwrap();
+        assert_eq!(frames.len(), 1);
+
+        let decoded = BluetoothMetricsBatch::parse_from_bytes(&frames[0]).unwrap();
+        assert_eq!(decoded.events.len(), 3);
+    }
+
+    #[test]
+    fn flush_of_empty_batch_is_a_noop() {
+        let sink = CapturingSink::default();
+        let mut batch: Vec<MetricsEvent> = Vec::new();
+
+        flush_batch(&sink, &mut batch);
+
+        assert!(sink.frames.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+//! Concrete [`MetricsSink`] implementations.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::bindings::root as bindings;
+use crate::metrics::MetricsSink;
+
+/// Forwards encoded frames to the platform statsd pipeline through the C++
+/// bridge in `bindings`. This is the production sink on device.
+pub struct StatsdSink;
+
+impl StatsdSink {
+    pub fn new() -> Self {
+        StatsdSink
+    }
+}
+
+impl Default for StatsdSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsSink for StatsdSink {
+    fn flush(&self, frame: &[u8]) {
+        // SAFETY: the bridge copies the slice synchronously; the pointer and
+        // length are only valid for the duration of the call, which the C++
+        // side respects.
+        unsafe {
+            bindings::bluetooth_metrics_report(frame.as_ptr(), frame.len());
+        }
+    }
+}
+
+/// Appends length-prefixed frames to a file for offline analysis. Each frame is
+/// preceded by its big-endian `u32` length so a reader can split the stream.
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    pub fn new<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FileSink { file: Mutex::new(file) })
+    }
+}
+
+impl MetricsSink for FileSink {
+    fn flush(&self, frame: &[u8]) {
+        let mut file = match self.file.lock() {
+            Ok(file) => file,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let len = (frame.len() as u32).to_be_bytes();
+        if let Err(e) = file.write_all(&len).and_then(|_| file.write_all(frame)) {
+            log::warn!("failed to write metrics frame: {}", e);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! GATT server profile shim.
+
+use crate::btif::{BtStatus, RawAddress, Uuid};
+
+/// Events the C++ GATT server delivers to Rust. The tuple layout matches the
+/// order the FFI callbacks pass their arguments.
+pub enum GattServerCallbacks {
+    /// `status`, `server_if`, `app_uuid`.
+    Register(BtStatus, i32, Uuid),
+    /// `conn_id`, `server_if`, `connected`, `addr`.
+    Connection(i32, i32, bool, RawAddress),
+    /// `conn_id`, `trans_id`, `addr`, `attr_handle`, `offset`, `need_rsp`,
+    /// `is_prep`, `value`.
+    RequestWrite(i32, i32, RawAddress, i32, i32, bool, bool, Vec<u8>),
+}
+
+/// Receives GATT server callbacks, parses their payloads, and forwards them to
+/// the registered handler.
+pub struct GattServerDispatch {
+    handler: Box<dyn Fn(GattServerCallbacks) + Send + Sync>,
+}
+
+impl GattServerDispatch {
+    /// Build a dispatch that forwards parsed callbacks to `handler`.
+    pub fn new(handler: Box<dyn Fn(GattServerCallbacks) + Send + Sync>) -> Self {
+        GattServerDispatch { handler }
+    }
+
+    /// Build a dispatch with a handler that discards every event. Used by the
+    /// fuzz targets, which care only about the Rust-side parse surface, not
+    /// about any outbound reply.
+    pub fn new_for_fuzz() -> Self {
+        GattServerDispatch::new(Box::new(|_| {}))
+    }
+
+    /// Parse an event's payload and forward it to the handler. Must tolerate
+    /// arbitrary bytes from a remote peer without panicking.
+    pub fn dispatch(&self, cb: GattServerCallbacks) {
+        if let GattServerCallbacks::RequestWrite(.., offset, _, _, value) = &cb {
+            // A prepared write carries a length-prefixed attribute fragment
+            // starting at `offset`; reject anything that runs off the buffer.
+            let _ = parse_prepared_write(*offset, value);
+        }
+        (self.handler)(cb);
+    }
+}
+
+/// A parsed prepared-write fragment: the attribute bytes at the requested
+/// offset. Returns `None` if the offset or length prefix is out of range.
+fn parse_prepared_write(offset: i32, value: &[u8]) -> Option<&[u8]> {
+    let start = usize::try_from(offset).ok()?;
+    let body = value.get(start..)?;
+    if body.len() < 2 {
+        return None;
+    }
+    let len = u16::from_le_bytes([body[0], body[1]]) as usize;
+    body.get(2..2 + len)
+}
@@ -0,0 +1,8 @@
+//! Bluetooth profile shims.
+//!
+//! Each submodule wraps one profile interface: it owns a dispatcher that the
+//! C++ callbacks feed events into, parses the incoming payloads on the Rust
+//! side, and forwards them to the registered handler.
+
+pub mod gatt;
+pub mod sdp;
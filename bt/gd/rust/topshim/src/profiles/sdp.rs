@@ -0,0 +1,88 @@
+//! SDP (Service Discovery Protocol) profile shim.
+
+use crate::btif::{BtStatus, RawAddress, Uuid};
+
+/// A parsed SDP record: a list of `(attribute id, attribute value)` pairs read
+/// out of the length-prefixed blob a remote peer returns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SdpRecord {
+    pub attributes: Vec<(u16, Vec<u8>)>,
+}
+
+impl SdpRecord {
+    /// Parse a record out of the raw attribute blob. The wire layout is a
+    /// sequence of `[u16 attr_id][u16 len][len bytes]` entries; parsing stops
+    /// at the first truncated entry. Never panics on malformed input.
+    pub fn from_bytes(buf: &[u8]) -> SdpRecord {
+        let mut attributes = Vec::new();
+        let mut i = 0usize;
+        while i + 4 <= buf.len() {
+            let id = u16::from_be_bytes([buf[i], buf[i + 1]]);
+            let len = u16::from_be_bytes([buf[i + 2], buf[i + 3]]) as usize;
+            i += 4;
+            let end = match i.checked_add(len) {
+                Some(end) if end <= buf.len() => end,
+                _ => break,
+            };
+            attributes.push((id, buf[i..end].to_vec()));
+            i = end;
+        }
+        SdpRecord { attributes }
+    }
+}
+
+/// Events the C++ SDP client delivers to Rust.
+pub enum SdpCallbacks {
+    /// `status`, `addr`, `uuid`, parsed record.
+    SearchComplete(BtStatus, RawAddress, Uuid, SdpRecord),
+}
+
+/// Receives SDP callbacks and forwards them to the registered handler.
+pub struct SdpDispatch {
+    handler: Box<dyn Fn(SdpCallbacks) + Send + Sync>,
+}
+
+impl SdpDispatch {
+    /// Build a dispatch that forwards callbacks to `handler`.
+    pub fn new(handler: Box<dyn Fn(SdpCallbacks) + Send + Sync>) -> Self {
+        SdpDispatch { handler }
+    }
+
+    /// Build a dispatch with a handler that discards every event, for fuzzing.
+    pub fn new_for_fuzz() -> Self {
+        SdpDispatch::new(Box::new(|_| {}))
+    }
+
+    /// Forward an event to the handler.
+    pub fn dispatch(&self, cb: SdpCallbacks) {
+        (self.handler)(cb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_well_formed_attributes() {
+        // id=0x0001 len=2 [aa bb], id=0x0004 len=1 [cc]
+        let buf = [0x00, 0x01, 0x00, 0x02, 0xaa, 0xbb, 0x00, 0x04, 0x00, 0x01, 0xcc];
+        let record = SdpRecord::from_bytes(&buf);
+        assert_eq!(
+            record.attributes,
+            vec![(0x0001, vec![0xaa, 0xbb]), (0x0004, vec![0xcc])]
+        );
+    }
+
+    #[test]
+    fn stops_at_truncated_length() {
+        // Declares a 4-byte value but only 1 byte follows: the entry is dropped.
+        let buf = [0x00, 0x01, 0x00, 0x04, 0xaa];
+        assert!(SdpRecord::from_bytes(&buf).attributes.is_empty());
+    }
+
+    #[test]
+    fn empty_input_yields_empty_record() {
+        assert_eq!(SdpRecord::from_bytes(&[]), SdpRecord::default());
+    }
+}
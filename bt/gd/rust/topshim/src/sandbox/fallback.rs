@@ -0,0 +1,18 @@
+//! No-op sandbox backend for platforms without seccomp-bpf.
+//!
+//! There is no mechanism to confine the process here, so a strict profile
+//! cannot be honored: rather than silently leave the process unconfined we
+//! report the first operation we cannot enforce. Permissive profiles, whose
+//! whole point is to run unconfined, succeed.
+
+use super::{Enforcement, Profile, SandboxError};
+
+pub fn activate(profile: &Profile) -> Result<(), SandboxError> {
+    match profile.enforcement() {
+        Enforcement::Permissive => Ok(()),
+        Enforcement::Strict => match profile.allowed_operations().first() {
+            Some(op) => Err(SandboxError::UnsupportedOperation(op.clone())),
+            None => Ok(()),
+        },
+    }
+}
@@ -0,0 +1,129 @@
+//! Syscall sandbox for the Bluetooth process.
+//!
+//! The profile handlers in `profiles` parse attacker-controlled payloads, so as
+//! defense-in-depth we confine the process to a minimal resource set once the
+//! stack has finished opening the file descriptors it needs. The design follows
+//! `gaol`: a [`Profile`] is a list of allowed [`Operation`]s, which a
+//! platform backend compiles into an enforcement mechanism and installs via
+//! [`ChildSandbox::activate`].
+//!
+//! On Linux/Android the backend is a seccomp-bpf filter installed with
+//! `prctl(PR_SET_SECCOMP, SECCOMP_MODE_FILTER)`. On every other platform the
+//! backend is a no-op that accepts any profile.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use linux as backend;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod fallback;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use fallback as backend;
+
+/// A resource the confined process is permitted to access. Each variant maps to
+/// a bounded set of syscalls that the backend is allowed to let through.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Read already-accessible files (config, sysfs). seccomp-bpf filters on
+    /// syscall numbers, not path strings — the filtered arg is a userspace
+    /// pointer the kernel will not dereference — so this cannot be scoped to a
+    /// single path and grants the read syscalls process-wide.
+    FileRead,
+    /// Open outbound network connections (AF_INET/AF_INET6/AF_BLUETOOTH).
+    NetworkOutbound,
+    /// Read non-sensitive system information (uname, sysconf and friends).
+    SystemInfoRead,
+}
+
+/// Errors raised while building or installing a sandbox.
+#[derive(Debug)]
+pub enum SandboxError {
+    /// The backend cannot express one of the requested operations as a policy.
+    UnsupportedOperation(Operation),
+    /// The kernel rejected the compiled policy (errno attached).
+    InstallFailed(i32),
+}
+
+impl std::fmt::Display for SandboxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SandboxError::UnsupportedOperation(op) => {
+                write!(f, "sandbox cannot express operation {:?}", op)
+            }
+            SandboxError::InstallFailed(errno) => {
+                write!(f, "sandbox install failed (errno {})", errno)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SandboxError {}
+
+/// How strictly a profile should be enforced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Enforcement {
+    /// Kill the process on a disallowed syscall. This is the production mode.
+    Strict,
+    /// Log and allow disallowed syscalls. Useful while widening the allowlist
+    /// during development; never ship this.
+    Permissive,
+}
+
+/// A description of what a confined process is allowed to do.
+#[derive(Clone, Debug)]
+pub struct Profile {
+    allowed: Vec<Operation>,
+    enforcement: Enforcement,
+}
+
+impl Profile {
+    /// Build a profile from a list of allowed operations, enforced strictly.
+    pub fn new(allowed: Vec<Operation>) -> Self {
+        Profile { allowed, enforcement: Enforcement::Strict }
+    }
+
+    /// Switch this profile into the permissive (log-only) fallback mode.
+    pub fn permissive(mut self) -> Self {
+        self.enforcement = Enforcement::Permissive;
+        self
+    }
+
+    pub fn allowed_operations(&self) -> &[Operation] {
+        &self.allowed
+    }
+
+    pub fn enforcement(&self) -> Enforcement {
+        self.enforcement
+    }
+
+    /// Turn this profile into the handle used to confine the current process.
+    pub fn into_sandbox(self) -> ChildSandbox {
+        ChildSandbox { profile: self }
+    }
+}
+
+/// A sandbox that confines the thread that calls [`ChildSandbox::activate`].
+pub struct ChildSandbox {
+    profile: Profile,
+}
+
+impl ChildSandbox {
+    /// Transition the current thread into the confined state.
+    ///
+    /// Returns [`SandboxError::UnsupportedOperation`] if any requested operation
+    /// cannot be expressed by the platform backend, and
+    /// [`SandboxError::InstallFailed`] if the kernel rejects the policy. On a
+    /// platform with no backend this succeeds without doing anything.
+    pub fn activate(&self) -> Result<(), SandboxError> {
+        backend::activate(&self.profile)
+    }
+}
+
+/// The baseline profile the Bluetooth process runs under: it may read its own
+/// configuration, talk to the controller over its already-open sockets, and
+/// query basic system info — nothing else.
+pub fn bluetooth_profile() -> ChildSandbox {
+    Profile::new(vec![Operation::FileRead, Operation::NetworkOutbound, Operation::SystemInfoRead])
+        .into_sandbox()
+}
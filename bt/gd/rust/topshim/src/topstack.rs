@@ -0,0 +1,169 @@
+//! Stack on top of the Bluetooth interface shim.
+//!
+//! This owns the process-wide facilities that the rest of topshim builds on:
+//! the executor that `btif` and `profiles` schedule their handlers onto, and
+//! the one-time process hardening we apply before that executor starts.
+//!
+//! The executor is abstracted behind the [`Runtime`] trait so the stack can be
+//! driven by an externally supplied executor — for tests, for embedding in a
+//! host process that already owns a reactor, or for a current-thread mode on
+//! constrained devices. [`get_runtime`] returns the installed runtime, falling
+//! back to a default multi-threaded tokio instance. A host installs its own
+//! once at startup with [`set_runtime`]; profile spawn helpers take the handle
+//! explicitly so unit tests can run state machines on a paused/mock clock.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use tokio::runtime::{Builder, Runtime as TokioRt};
+
+use crate::sandbox::{self, SandboxError};
+
+/// A fire-and-forget task scheduled onto a [`Runtime`].
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// An executor topshim can schedule handlers onto. Object-safe so it can be
+/// held as an `Arc<dyn Runtime>` and swapped out at startup.
+pub trait Runtime: Send + Sync {
+    /// Schedule a task to run to completion in the background.
+    fn spawn(&self, task: BoxFuture);
+
+    /// Run a task to completion, blocking the calling thread until it finishes.
+    fn block_on(&self, task: BoxFuture);
+}
+
+/// The default tokio-backed runtime.
+pub struct TokioRuntime {
+    rt: Arc<TokioRt>,
+}
+
+impl TokioRuntime {
+    /// Build the multi-threaded runtime topshim uses by default. One worker is
+    /// enough; handlers are light and mostly bounce work back to C++.
+    pub fn new() -> Self {
+        let rt = Builder::new_multi_thread()
+            .worker_threads(1)
+            .max_blocking_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        TokioRuntime { rt: Arc::new(rt) }
+    }
+
+    /// Build a single-threaded, current-thread runtime for constrained devices
+    /// or deterministic tests.
+    pub fn new_current_thread() -> Self {
+        let rt = Builder::new_current_thread().enable_all().build().unwrap();
+        TokioRuntime { rt: Arc::new(rt) }
+    }
+
+    /// Borrow the underlying tokio runtime, e.g. to obtain a `Handle` or to
+    /// drive a paused clock from a test.
+    pub fn inner(&self) -> &TokioRt {
+        &self.rt
+    }
+}
+
+impl Default for TokioRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runtime for TokioRuntime {
+    fn spawn(&self, task: BoxFuture) {
+        self.rt.spawn(task);
+    }
+
+    fn block_on(&self, task: BoxFuture) {
+        self.rt.block_on(task);
+    }
+}
+
+lazy_static! {
+    // The lazily-built default, used whenever a host has not installed its own.
+    static ref DEFAULT_RT: Arc<dyn Runtime> = Arc::new(TokioRuntime::new());
+    // The host-installed runtime, set at most once via `set_runtime`.
+    static ref INSTALLED_RT: RwLock<Option<Arc<dyn Runtime>>> = RwLock::new(None);
+}
+
+/// Install the runtime topshim should schedule onto. Must be called once, at
+/// startup, before any handler is spawned; calling it twice panics.
+pub fn set_runtime(runtime: Arc<dyn Runtime>) {
+    let mut installed = INSTALLED_RT.write().unwrap();
+    assert!(installed.is_none(), "topshim runtime already installed");
+    *installed = Some(runtime);
+}
+
+/// Get the installed runtime, or the default tokio runtime if none was set.
+pub fn get_runtime() -> Arc<dyn Runtime> {
+    match INSTALLED_RT.read().unwrap().as_ref() {
+        Some(runtime) => runtime.clone(),
+        None => DEFAULT_RT.clone(),
+    }
+}
+
+/// Spawn a task onto the global runtime. Convenience wrapper for callers that
+/// do not thread a handle explicitly.
+pub fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    get_runtime().spawn(Box::pin(future));
+}
+
+/// Spawn a task onto an explicitly supplied runtime. Profile state machines use
+/// this so a test can hand in a current-thread runtime on a paused clock and
+/// drive them deterministically.
+pub fn spawn_on<F>(runtime: &Arc<dyn Runtime>, future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    runtime.spawn(Box::pin(future));
+}
+
+/// Confine the Bluetooth process before the runtime starts handling peer data.
+///
+/// This must run after all HCI/socket file descriptors have been opened but
+/// before any profile handler can touch attacker-controlled payloads. On a
+/// platform without a sandbox backend this is a no-op; if the requested
+/// confinement cannot be expressed it returns an error so the caller can decide
+/// whether to fall back to the permissive profile or abort.
+pub fn activate_sandbox() -> Result<(), SandboxError> {
+    sandbox::bluetooth_profile().activate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn block_on_runs_future_to_completion() {
+        let runtime: Arc<dyn Runtime> = Arc::new(TokioRuntime::new_current_thread());
+        let (tx, rx) = mpsc::channel();
+        runtime.block_on(Box::pin(async move {
+            tx.send(42).unwrap();
+        }));
+        assert_eq!(rx.recv().unwrap(), 42);
+    }
+
+    #[test]
+    fn spawn_on_schedules_onto_the_supplied_runtime() {
+        // A profile state machine can be driven deterministically by handing it
+        // an explicit current-thread runtime rather than the global one.
+        let runtime: Arc<dyn Runtime> = Arc::new(TokioRuntime::new_current_thread());
+        let (tx, rx) = mpsc::channel();
+
+        spawn_on(&runtime, async move {
+            tx.send(7).unwrap();
+        });
+        // The queued task only makes progress while the runtime is driven.
+        runtime.block_on(Box::pin(async {
+            tokio::task::yield_now().await;
+        }));
+
+        assert_eq!(rx.recv().unwrap(), 7);
+    }
+}